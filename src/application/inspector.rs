@@ -1,5 +1,10 @@
-use crate::{graph::graph_editor_egui::editor_state::GraphEditorState, prelude::*};
+use crate::{
+    graph::graph_editor_egui::editor_state::GraphEditorState,
+    prelude::*,
+    rendergraph::egui_routine_custom::{EguiCustomCallback, ThumbnailTarget},
+};
 use egui::*;
+use std::collections::HashMap;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum InspectorTab {
@@ -17,10 +22,10 @@ impl InspectorTabs {
     pub fn new() -> Self {
         Self {
             current_view: InspectorTab::Properties,
-            properties: PropertiesTab {},
-            spreadsheet: SpreadsheetTab {
-                current_view: SpreadsheetViews::Vertices,
+            properties: PropertiesTab {
+                thumbnails: NodeThumbnails::new(),
             },
+            spreadsheet: SpreadsheetTab::new(),
         }
     }
 }
@@ -31,7 +36,73 @@ impl Default for InspectorTabs {
     }
 }
 
-pub struct PropertiesTab {}
+pub struct PropertiesTab {
+    thumbnails: NodeThumbnails,
+}
+
+/// A small live 3D preview of a node's output mesh, rendered via an egui
+/// paint callback: during egui tessellation, a miniature flat-shaded scene
+/// (the mesh, a fixed directional light, and an auto-framed camera) is drawn
+/// with its own small wgpu pipeline, not with rend3's renderer, straight into
+/// the render pass egui hands the callback, scissored to the widget's rect.
+/// See [`ThumbnailTarget`]'s own doc comment for why there's no offscreen
+/// texture involved.
+struct NodeThumbnails {
+    /// One `ThumbnailTarget` per widget id, so each node's render pipeline
+    /// is created once and reused across frames. The mesh is still
+    /// re-triangulated, re-framed and re-uploaded to fresh vertex/uniform
+    /// buffers every frame.
+    targets: HashMap<Id, ThumbnailTarget>,
+}
+
+impl NodeThumbnails {
+    fn new() -> Self {
+        Self {
+            targets: HashMap::new(),
+        }
+    }
+
+    /// Draws a `size`-sized live preview of `mesh` at the widget id `id`,
+    /// reusing the pooled `ThumbnailTarget` across frames. Draws a "No mesh"
+    /// placeholder instead when there's nothing to preview.
+    fn show(&mut self, ui: &mut Ui, id: Id, size: Vec2, mesh: Option<&HalfEdgeMesh>) {
+        let (rect, _response) = ui.allocate_exact_size(size, Sense::hover());
+
+        let mesh = match mesh {
+            Some(mesh) => mesh,
+            None => {
+                ui.painter()
+                    .rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+                ui.painter().text(
+                    rect.center(),
+                    Align2::CENTER_CENTER,
+                    "No mesh",
+                    FontId::default(),
+                    ui.visuals().weak_text_color(),
+                );
+                return;
+            }
+        };
+
+        let target = self
+            .targets
+            .entry(id)
+            .or_insert_with(|| ThumbnailTarget::new(rect));
+        target.resize(rect);
+        target.update_mesh(mesh);
+
+        // Scissored to `rect` by the routine, so the mini-viewport never
+        // draws outside of its allotted widget space.
+        ui.painter()
+            .add(EguiCustomCallback::new(rect, target.clone()));
+    }
+}
+
+impl PropertiesTab {
+    /// Height, in points, reserved above the parameter list for the mesh
+    /// thumbnail.
+    const THUMBNAIL_HEIGHT: f32 = 140.0;
+}
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum SpreadsheetViews {
@@ -42,6 +113,23 @@ pub enum SpreadsheetViews {
 
 pub struct SpreadsheetTab {
     pub current_view: SpreadsheetViews,
+    /// Only rows with a cell containing this text (case-insensitive) are
+    /// shown. Empty means "show everything".
+    filter: String,
+    /// The column currently sorted on, and whether that sort is ascending.
+    /// Reset to `None` (insertion order) whenever the view changes, since a
+    /// sort on e.g. "twin" doesn't carry over to the Faces table.
+    sort: Option<(usize, bool)>,
+}
+
+impl SpreadsheetTab {
+    fn new() -> Self {
+        Self {
+            current_view: SpreadsheetViews::Vertices,
+            filter: String::new(),
+            sort: None,
+        }
+    }
 }
 
 impl InspectorTabs {
@@ -65,12 +153,31 @@ impl InspectorTabs {
         });
         ui.separator();
         match self.current_view {
-            InspectorTab::Properties => self.properties.ui(ui, editor_state),
+            InspectorTab::Properties => self.properties.ui(ui, mesh, editor_state),
             InspectorTab::Spreadsheet => self.spreadsheet.ui(ui, mesh),
         }
     }
 }
 
+/// Formats a dense index as e.g. `"3"`, or `"-"` when there isn't one (a
+/// half-edge's `twin`/`face` can be absent on a border edge). Shared by
+/// every spreadsheet column that references an optional handle.
+fn format_index_or_dash(idx: Option<usize>) -> String {
+    idx.map(|i| i.to_string()).unwrap_or_else(|| "-".into())
+}
+
+/// Orders two pre-formatted spreadsheet cells: numerically when both sides
+/// parse as a number (so "10" sorts after "2", and a right-aligned
+/// "-1.000"/" 2.000" compares by value instead of by character), falling
+/// back to a plain string compare otherwise (e.g. the "vertices" column,
+/// which lists comma-separated vertex indices).
+fn compare_cells(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
 pub fn tiny_checkbox(ui: &mut Ui, value: &mut bool) {
     let mut child_ui = ui.child_ui(ui.available_rect_before_wrap(), *ui.layout());
     child_ui.spacing_mut().icon_spacing = 0.0;
@@ -80,9 +187,18 @@ pub fn tiny_checkbox(ui: &mut Ui, value: &mut bool) {
 }
 
 impl PropertiesTab {
-    fn ui(&self, ui: &mut Ui, editor_state: &mut GraphEditorState) {
+    fn ui(&mut self, ui: &mut Ui, mesh: Option<&HalfEdgeMesh>, editor_state: &mut GraphEditorState) {
         let graph = &mut editor_state.graph;
         if let Some(node) = editor_state.selected_node {
+            let thumbnail_width = ui.available_width();
+            self.thumbnails.show(
+                ui,
+                Id::new("node-thumbnail").with(node),
+                vec2(thumbnail_width, Self::THUMBNAIL_HEIGHT),
+                mesh,
+            );
+            ui.separator();
+
             let node = &graph[node];
             let inputs = node.inputs.clone();
             ui.vertical(|ui| {
@@ -104,6 +220,7 @@ impl PropertiesTab {
 }
 impl SpreadsheetTab {
     fn ui(&mut self, ui: &mut Ui, mesh: Option<&HalfEdgeMesh>) {
+        let previous_view = self.current_view;
         ui.horizontal(|ui| {
             ui.selectable_value(
                 &mut self.current_view,
@@ -116,63 +233,235 @@ impl SpreadsheetTab {
                 SpreadsheetViews::Halfedges,
                 "Half edges",
             );
+            ui.separator();
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut self.filter);
         });
+        if self.current_view != previous_view {
+            self.sort = None;
+        }
 
-        if let Some(mesh) = mesh {
-            let scroll_area = ScrollArea::both().auto_shrink([false, false]);
-            scroll_area.show(ui, |ui| match self.current_view {
-                SpreadsheetViews::Vertices => {
-                    // Vertex spreadsheet
-                    Grid::new("vertex-spreadsheet")
-                        .striped(true)
-                        .num_columns(4)
-                        .show(ui, |ui| {
-                            ui.label("");
-                            ui.label("x");
-                            ui.label("y");
-                            ui.label("z");
-                            ui.end_row();
-
-                            for (idx, (_, v)) in mesh.iter_vertices().enumerate() {
-                                ui.label(idx.to_string());
-                                ui.monospace(format!("{: >6.3}", v.position.x));
-                                ui.monospace(format!("{: >6.3}", v.position.y));
-                                ui.monospace(format!("{: >6.3}", v.position.z));
-                                ui.end_row();
-                            }
-                        })
+        let mesh = match mesh {
+            Some(mesh) => mesh,
+            None => return,
+        };
+
+        // Handles are opaque slotmap keys, so to show them as the same
+        // reader-friendly row numbers (e.g. "3") the "#" column uses, every
+        // handle kind gets its own dense index built up front.
+        let vertex_idx: HashMap<VertexId, usize> = mesh
+            .iter_vertices()
+            .enumerate()
+            .map(|(i, (id, _))| (id, i))
+            .collect();
+        let halfedge_idx: HashMap<HalfEdgeId, usize> = mesh
+            .iter_halfedges()
+            .enumerate()
+            .map(|(i, (id, _))| (id, i))
+            .collect();
+        let face_idx: HashMap<FaceId, usize> = mesh
+            .iter_faces()
+            .enumerate()
+            .map(|(i, (id, _))| (id, i))
+            .collect();
+
+        let fmt_vertex = |v: VertexId| vertex_idx[&v].to_string();
+        let fmt_halfedge = |h: HalfEdgeId| halfedge_idx[&h].to_string();
+        let fmt_opt_halfedge =
+            |h: Option<HalfEdgeId>| format_index_or_dash(h.map(|h| halfedge_idx[&h]));
+        let fmt_opt_face = |f: Option<FaceId>| format_index_or_dash(f.map(|f| face_idx[&f]));
+
+        let (headers, rows): (Vec<String>, Vec<Vec<String>>) = match self.current_view {
+            SpreadsheetViews::Vertices => {
+                let channels = mesh.channels.vertex_channel_names();
+                let mut headers = vec!["#".to_owned(), "x".to_owned(), "y".to_owned(), "z".to_owned()];
+                headers.extend(channels.iter().cloned());
+
+                let rows = mesh
+                    .iter_vertices()
+                    .enumerate()
+                    .map(|(idx, (id, v))| {
+                        let mut row = vec![
+                            idx.to_string(),
+                            format!("{: >6.3}", v.position.x),
+                            format!("{: >6.3}", v.position.y),
+                            format!("{: >6.3}", v.position.z),
+                        ];
+                        row.extend(channels.iter().map(|name| {
+                            mesh.channels
+                                .vertex_channel_value_string(name, id)
+                                .unwrap_or_default()
+                        }));
+                        row
+                    })
+                    .collect();
+                (headers, rows)
+            }
+            SpreadsheetViews::Halfedges => {
+                let channels = mesh.channels.halfedge_channel_names();
+                let mut headers = vec![
+                    "#".to_owned(),
+                    "twin".to_owned(),
+                    "next".to_owned(),
+                    "vertex".to_owned(),
+                    "face".to_owned(),
+                ];
+                headers.extend(channels.iter().cloned());
+
+                let rows = mesh
+                    .iter_halfedges()
+                    .enumerate()
+                    .map(|(idx, (id, h))| {
+                        let mut row = vec![
+                            idx.to_string(),
+                            fmt_opt_halfedge(h.twin),
+                            fmt_halfedge(h.next),
+                            fmt_vertex(h.vertex),
+                            fmt_opt_face(h.face),
+                        ];
+                        row.extend(channels.iter().map(|name| {
+                            mesh.channels
+                                .halfedge_channel_value_string(name, id)
+                                .unwrap_or_default()
+                        }));
+                        row
+                    })
+                    .collect();
+                (headers, rows)
+            }
+            SpreadsheetViews::Faces => {
+                let channels = mesh.channels.face_channel_names();
+                let mut headers = vec!["#".to_owned(), "vertices".to_owned(), "count".to_owned()];
+                headers.extend(channels.iter().cloned());
+
+                let rows = mesh
+                    .iter_faces()
+                    .enumerate()
+                    .map(|(idx, (id, _))| {
+                        let loop_vertices = mesh.face_vertices(id);
+                        let vertex_list = loop_vertices
+                            .iter()
+                            .copied()
+                            .map(fmt_vertex)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let mut row =
+                            vec![idx.to_string(), vertex_list, loop_vertices.len().to_string()];
+                        row.extend(channels.iter().map(|name| {
+                            mesh.channels
+                                .face_channel_value_string(name, id)
+                                .unwrap_or_default()
+                        }));
+                        row
+                    })
+                    .collect();
+                (headers, rows)
+            }
+        };
+
+        let scroll_area = ScrollArea::both().auto_shrink([false, false]);
+        scroll_area.show(ui, |ui| {
+            Self::render_table(ui, &headers, rows, &self.filter, &mut self.sort);
+        });
+    }
+
+    /// Renders `rows` (each already matching `headers` in column count) as a
+    /// striped grid. Rows not containing `filter` in any cell (case
+    /// insensitive) are skipped, and clicking a header sorts by that column,
+    /// toggling direction on repeated clicks.
+    fn render_table(
+        ui: &mut Ui,
+        headers: &[String],
+        mut rows: Vec<Vec<String>>,
+        filter: &str,
+        sort: &mut Option<(usize, bool)>,
+    ) {
+        if let Some((col, ascending)) = *sort {
+            rows.sort_by(|a, b| {
+                let ord = compare_cells(&a[col], &b[col]);
+                if ascending {
+                    ord
+                } else {
+                    ord.reverse()
                 }
-                SpreadsheetViews::Halfedges => {
-                    // Halfedge spreadsheet
-                    Grid::new("halfedge-spreadsheet")
-                        .striped(true)
-                        .num_columns(1)
-                        .show(ui, |ui| {
-                            ui.label("");
-                            ui.end_row();
-
-                            for (idx, _) in mesh.iter_halfedges().enumerate() {
-                                ui.label(idx.to_string());
-                                ui.end_row();
+            });
+        }
+
+        let filter = filter.to_lowercase();
+
+        Grid::new("mesh-spreadsheet")
+            .striped(true)
+            .num_columns(headers.len())
+            .show(ui, |ui| {
+                for (col, header) in headers.iter().enumerate() {
+                    if ui.button(header).clicked() {
+                        *sort = Some(match sort {
+                            Some((sorted_col, ascending)) if *sorted_col == col => {
+                                (col, !*ascending)
                             }
-                        })
+                            _ => (col, true),
+                        });
+                    }
                 }
-                SpreadsheetViews::Faces => {
-                    // Face spreadsheet
-                    Grid::new("halfedge-spreadsheet")
-                        .striped(true)
-                        .num_columns(1)
-                        .show(ui, |ui| {
-                            ui.label("");
-                            ui.end_row();
-
-                            for (idx, _) in mesh.iter_faces().enumerate() {
-                                ui.label(idx.to_string());
-                                ui.end_row();
-                            }
-                        })
+                ui.end_row();
+
+                for row in &rows {
+                    if !filter.is_empty()
+                        && !row.iter().any(|cell| cell.to_lowercase().contains(&filter))
+                    {
+                        continue;
+                    }
+                    for cell in row {
+                        ui.monospace(cell);
+                    }
+                    ui.end_row();
                 }
             });
-        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn compare_cells_orders_numbers_by_value_not_by_character() {
+        assert_eq!(compare_cells("2", "10"), Ordering::Less);
+        assert_eq!(compare_cells(" 2.000", "-1.000"), Ordering::Greater);
+    }
+
+    #[test]
+    fn compare_cells_falls_back_to_string_compare_for_non_numeric_cells() {
+        assert_eq!(compare_cells("#2, #10", "#2, #3"), Ordering::Less);
+        assert_eq!(compare_cells("-", "-"), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_cells_treats_one_sided_numeric_parse_as_non_numeric() {
+        // One side parses as a number and the other doesn't (e.g. a "-"
+        // placeholder next to a real index): falls back to string compare
+        // rather than panicking or silently picking one side.
+        assert_eq!(compare_cells("-", "3"), "-".cmp("3"));
+    }
+
+    #[test]
+    fn compare_cells_sort_as_used_by_render_table_is_stable_on_ties() {
+        let mut rows = vec![
+            vec!["0".to_owned(), "a".to_owned()],
+            vec!["0".to_owned(), "b".to_owned()],
+            vec!["0".to_owned(), "c".to_owned()],
+        ];
+        rows.sort_by(|a, b| compare_cells(&a[0], &b[0]));
+        assert_eq!(
+            rows.iter().map(|r| r[1].as_str()).collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn format_index_or_dash_formats_present_and_missing_indices() {
+        assert_eq!(format_index_or_dash(Some(3)), "3");
+        assert_eq!(format_index_or_dash(None), "-");
     }
 }
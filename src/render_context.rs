@@ -1,4 +1,4 @@
-use std::{rc::Rc, sync::Arc};
+use std::{collections::HashMap, rc::Rc, sync::Arc};
 
 use crate::{
     graph::graph_editor_egui::viewport_manager::AppViewports, prelude::*,
@@ -16,23 +16,246 @@ use wgpu::{Features, Surface, TextureFormat};
 
 use crate::rendergraph;
 
+/// Identifies a single 3D viewport. Cameras are tracked per-viewport so that
+/// split layouts (multiple 3D views open at once) each get their own
+/// perspective instead of sharing rend3's single global camera.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ViewportId(pub u64);
+
+impl ViewportId {
+    /// The id of the application's single built-in 3D viewport, the only
+    /// one wired into the editor's panel layout. Any other id shows up in
+    /// `RenderContext::cameras` only once `set_viewport_camera` adds it.
+    pub const MAIN_3D: ViewportId = ViewportId(0);
+}
+
+/// Per-viewport camera state. `aspect_ratio` is recomputed every frame from
+/// the viewport's current rect, rather than being set once globally, so that
+/// each viewport can have a different shape without stomping on the others.
+#[derive(Clone, Copy, Debug)]
+pub struct ViewportCamera {
+    pub view_matrix: Mat4,
+    pub vfov: f32,
+    pub near: f32,
+    pub aspect_ratio: f32,
+}
+
+impl Default for ViewportCamera {
+    fn default() -> Self {
+        Self {
+            view_matrix: Mat4::IDENTITY,
+            vfov: 60.0,
+            near: 0.1,
+            aspect_ratio: 1.0,
+        }
+    }
+}
+
+/// How a 3D viewport's pixels make it into the final surface image.
+///
+/// The node graph panel always needs [`OffscreenTexture`](Self::OffscreenTexture),
+/// since pan/zoom is implemented by sampling that intermediate texture at an
+/// offset/scale. The main 3D viewport doesn't pan/zoom like that, so it can
+/// use [`DirectToSurface`](Self::DirectToSurface) instead. Note this doesn't
+/// avoid an offscreen render target or a blit — rend3's PBR/tonemapping
+/// routines always render into a target sized to exactly their own
+/// resolution, so that allocation still happens either way, and the result
+/// still gets blitted into the surface either way. Both modes blit with the
+/// same render graph node, using `wgpu::RenderPass::set_viewport` + a
+/// scissor rect, following rerun's `ViewBuilder`. The only difference is
+/// where that node is recorded: right after the viewport's own passes in
+/// `DirectToSurface` mode, or by the window's egui routine just before it
+/// draws the main UI in `OffscreenTexture` mode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ViewportCompositeMode {
+    OffscreenTexture,
+    DirectToSurface,
+}
+
+impl Default for ViewportCompositeMode {
+    fn default() -> Self {
+        Self::OffscreenTexture
+    }
+}
+
+/// The node graph's zoom level. `render_frame` multiplies the screen size
+/// the node graph's egui routine is `resize`d to by this value, so larger
+/// values draw the graph smaller: `2.0` shows it at half size, and `1.0`
+/// (the minimum) at its natural size; no value draws it larger than that.
+/// The reciprocal needed for `pixels_per_point` lives in one place,
+/// [`Zoom::pixels_per_point`]. This is also the value that gets saved and
+/// restored across sessions as part of the editor config.
+#[derive(Clone, Copy, PartialEq, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Zoom(f32);
+
+impl Zoom {
+    pub const MIN: f32 = 1.0;
+    pub const MAX: f32 = 4.0;
+
+    pub fn new(scale: f32) -> Self {
+        Self(scale.clamp(Self::MIN, Self::MAX))
+    }
+
+    /// The factor the node graph's egui screen size is multiplied by; a
+    /// larger value zooms out.
+    pub fn scale(self) -> f32 {
+        self.0
+    }
+
+    /// The egui `pixels_per_point` this zoom level implies. This is the
+    /// inverse of `scale`, since doubling `scale` halves the size each egui
+    /// "point" is drawn at in the offscreen texture.
+    pub fn pixels_per_point(self) -> f32 {
+        1.0 / self.0
+    }
+
+    /// Applies a Ctrl+scroll delta, in scroll-wheel units, clamping the
+    /// result to `[Self::MIN, Self::MAX]`.
+    pub fn add_scroll_delta(&mut self, delta: f32) {
+        self.0 = (self.0 + delta * 0.01).clamp(Self::MIN, Self::MAX);
+    }
+}
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Where [`RenderConfig`] is read from and written to. Relative to the
+/// process's working directory, matching how this crate has no app-data-dir
+/// lookup (or a dependency that would provide one) anywhere else in this
+/// tree.
+const CONFIG_PATH: &str = "blackjack_config.json";
+
+/// Renderer/editor settings that persist across sessions. Thin today (just
+/// the node graph's zoom level) — expected to grow as more `RenderContext`
+/// state needs a "remember this for next time" home, at which point this is
+/// where it goes rather than scattering ad-hoc serde derives elsewhere.
+#[derive(Clone, Copy, PartialEq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct RenderConfig {
+    pub zoom: Zoom,
+}
+
+impl RenderConfig {
+    /// Loads the `zoom` half of [`RenderConfig`] from [`CONFIG_PATH`],
+    /// falling back to [`Zoom::default`] the same way [`Self::load_or_default`]
+    /// does. Meant to be called once at startup, wherever the caller creates
+    /// the `Zoom` it then passes into every `render_frame`/`render_frame_main`
+    /// call (see that parameter's own doc comment).
+    pub fn load_initial_zoom() -> Zoom {
+        Self::load_or_default(std::path::Path::new(CONFIG_PATH)).zoom
+    }
+
+    /// Reads `path`, falling back to `Self::default()` if it doesn't exist
+    /// yet or fails to parse (e.g. a config written by an older version).
+    pub fn load_or_default(path: &std::path::Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap())
+    }
+}
+
+/// What a single iteration of the per-viewport render loop in `render_frame`
+/// produced, depending on `RenderContext::viewport_composite_mode`.
+enum ViewportOutput {
+    Texture(r3::RenderTargetHandle),
+    DirectToSurface,
+}
+
+/// Everything a single OS window needs to have its own surface rendered and
+/// presented independently of every other window: its own `wgpu::Surface`,
+/// that surface's preferred format, and the egui routine that draws whatever
+/// content lives in that window.
+///
+/// Splitting this out of `RenderContext` (which used to own exactly one of
+/// each) is what lets a panel be torn off into its own native window: the
+/// panel keeps drawing the same egui content, just against a `WindowSurface`
+/// of its own instead of borrowing the main window's.
+pub struct WindowSurface {
+    pub surface: Arc<Surface>,
+    pub texture_format: TextureFormat,
+    pub egui_routine: rendergraph::egui_routine_custom::EguiCustomRoutine,
+}
+
+impl WindowSurface {
+    fn new(renderer: &Renderer, window: &winit::window::Window) -> Self {
+        let window_size = window.inner_size();
+        let surface = Arc::new(unsafe { renderer.instance.create_surface(&window) });
+        let texture_format = surface.get_preferred_format(&renderer.adapter).unwrap();
+        rend3::configure_surface(
+            &surface,
+            &renderer.device,
+            texture_format,
+            glam::UVec2::new(window_size.width, window_size.height),
+            rend3::types::PresentMode::Mailbox,
+        );
+
+        let egui_routine = rendergraph::egui_routine_custom::EguiCustomRoutine::new(
+            renderer,
+            texture_format,
+            SampleCount::One,
+            window_size.width,
+            window_size.height,
+            window.scale_factor() as f32,
+        );
+
+        Self {
+            surface,
+            texture_format,
+            egui_routine,
+        }
+    }
+}
+
 pub struct RenderContext {
     pub renderer: Arc<Renderer>,
 
     pub base_graph: r3::BaseRenderGraph,
     pub pbr_routine: r3::PbrRoutine,
     pub tonemapping_routine: r3::TonemappingRoutine,
-    /// The egui routine responsible for drawing the main application UI
-    pub main_egui_routine: rendergraph::egui_routine_custom::EguiCustomRoutine,
+
+    /// One surface (+ preferred format + egui routine) per live OS window,
+    /// keyed by winit's own window id. The main application window is always
+    /// present; `add_window` creates additional entries when a panel is torn
+    /// off into its own window.
+    windows: HashMap<winit::window::WindowId, WindowSurface>,
+    /// Id of the main application window, i.e. the one created alongside
+    /// this `RenderContext` in `new`.
+    main_window_id: winit::window::WindowId,
+
     /// The egui routine responsible for drawing the graph editor. This is
-    /// renderd to an offscreen texture so we can do pan / zoom.
+    /// rendered to an offscreen texture so we can do pan / zoom, independent
+    /// of which window it ends up composited into.
     pub graph_egui_routine: rendergraph::egui_routine_custom::EguiCustomRoutine,
     pub grid_routine: GridRoutine,
-    pub surface: Arc<Surface>,
-    pub texture_format: TextureFormat,
+    /// Shared pipeline/sampler for blitting an already-rendered texture into
+    /// a sub-rect of another render target, built once here and reused by
+    /// every viewport composite each frame instead of per-call.
+    blit_routine: rendergraph::egui_routine_custom::BlitRoutine,
 
     pub objects: Vec<ResourceHandle<Object>>,
     lights: Vec<ResourceHandle<DirectionalLight>>,
+
+    /// One camera per registered 3D viewport. See [`ViewportId`].
+    cameras: HashMap<ViewportId, ViewportCamera>,
+    /// Screen-space rect each non-[`MAIN_3D`](ViewportId::MAIN_3D) viewport
+    /// should be composited into, set via [`Self::set_viewport_rect`].
+    /// `MAIN_3D` doesn't go through this map: its rect always comes from
+    /// `AppViewports::view_3d`, since it's the one viewport already wired
+    /// into the editor's panel layout.
+    viewport_rects: HashMap<ViewportId, egui::Rect>,
+
+    /// How the main 3D viewport is composited into the final frame. The node
+    /// graph panel is not affected by this and always uses an offscreen
+    /// texture, since it needs one to support pan/zoom.
+    pub viewport_composite_mode: ViewportCompositeMode,
 }
 
 fn ambient_light() -> Vec4 {
@@ -50,17 +273,6 @@ impl RenderContext {
         ))
         .unwrap();
 
-        let surface = Arc::new(unsafe { iad.instance.create_surface(&window) });
-
-        let format = surface.get_preferred_format(&iad.adapter).unwrap();
-        rend3::configure_surface(
-            &surface,
-            &iad.device,
-            format,
-            glam::UVec2::new(window_size.width, window_size.height),
-            rend3::types::PresentMode::Mailbox,
-        );
-
         let renderer = r3::Renderer::new(
             iad,
             r3::Handedness::Left,
@@ -68,6 +280,9 @@ impl RenderContext {
         )
         .unwrap();
 
+        let main_window = WindowSurface::new(&renderer, window);
+        let format = main_window.texture_format;
+
         let base_graph = r3::BaseRenderGraph::new(&renderer);
         let mut data_core = renderer.data_core.lock();
         let pbr_routine = PbrRoutine::new(&renderer, &mut data_core, &base_graph.interfaces);
@@ -75,15 +290,6 @@ impl RenderContext {
             r3::TonemappingRoutine::new(&renderer, &base_graph.interfaces, format);
         drop(data_core); // Release the lock
 
-        let main_egui_routine = rendergraph::egui_routine_custom::EguiCustomRoutine::new(
-            &renderer,
-            format,
-            SampleCount::One,
-            window_size.width,
-            window_size.height,
-            window.scale_factor() as f32,
-        );
-
         let graph_egui_routine = rendergraph::egui_routine_custom::EguiCustomRoutine::new(
             &renderer,
             format,
@@ -94,22 +300,50 @@ impl RenderContext {
         );
 
         let grid_routine = GridRoutine::new(&renderer.device);
+        let blit_routine = rendergraph::egui_routine_custom::BlitRoutine::new(&renderer.device);
+
+        let main_window_id = window.id();
 
         RenderContext {
             renderer,
             pbr_routine,
             base_graph,
             tonemapping_routine,
-            main_egui_routine,
+            windows: HashMap::from([(main_window_id, main_window)]),
+            main_window_id,
             graph_egui_routine,
             grid_routine,
-            surface,
-            texture_format: format,
+            blit_routine,
             objects: vec![],
             lights: vec![],
+            cameras: HashMap::from([(ViewportId::MAIN_3D, ViewportCamera::default())]),
+            viewport_rects: HashMap::new(),
+            viewport_composite_mode: ViewportCompositeMode::default(),
+        }
+    }
+
+    /// Registers `window` as an additional render target, with its own
+    /// surface, preferred format and egui routine, so content (e.g. the node
+    /// graph, once torn off) can be rendered into it independently of the
+    /// main window.
+    pub fn add_window(&mut self, window: &winit::window::Window) -> winit::window::WindowId {
+        let id = window.id();
+        self.windows.insert(id, WindowSurface::new(&self.renderer, window));
+        id
+    }
+
+    /// Drops a window's surface and egui routine. Does nothing if `id` is
+    /// the main window, which must outlive the `RenderContext`.
+    pub fn remove_window(&mut self, id: winit::window::WindowId) {
+        if id != self.main_window_id {
+            self.windows.remove(&id);
         }
     }
 
+    pub fn main_window_id(&self) -> winit::window::WindowId {
+        self.main_window_id
+    }
+
     pub fn clear_objects(&mut self) {
         self.objects.clear();
     }
@@ -133,20 +367,57 @@ impl RenderContext {
         self.objects.push(self.renderer.add_object(object));
     }
 
+    /// Updates the view matrix for the main 3D viewport's camera. Kept
+    /// around, with its original signature, alongside
+    /// `set_viewport_camera` so existing call sites don't need to learn
+    /// about `ViewportId` just to keep compiling.
     pub fn set_camera(&mut self, view_matrix: Mat4) {
-        self.renderer.set_camera_data(rend3::types::Camera {
-            projection: rend3::types::CameraProjection::Perspective {
-                vfov: 60.0,
-                near: 0.1,
-            },
-            view: view_matrix,
-        });
+        self.set_viewport_camera(ViewportId::MAIN_3D, view_matrix);
+    }
+
+    /// Updates the view matrix for a single viewport's camera, creating it
+    /// with default perspective parameters if this is the first time we've
+    /// seen `viewport`.
+    pub fn set_viewport_camera(&mut self, viewport: ViewportId, view_matrix: Mat4) {
+        self.cameras.entry(viewport).or_default().view_matrix = view_matrix;
+    }
+
+    /// Registers the screen-space rect `viewport` should be composited into
+    /// during `render_frame`. Only needed for viewports other than
+    /// [`ViewportId::MAIN_3D`], whose rect already comes from
+    /// `AppViewports::view_3d`; a viewport with a camera but no rect
+    /// registered here is simply not rendered.
+    pub fn set_viewport_rect(&mut self, viewport: ViewportId, rect: egui::Rect) {
+        self.viewport_rects.insert(viewport, rect);
     }
 
+    /// Projects a world-space point into the screen-space pixel coordinates
+    /// of the main 3D viewport. Kept around, with its original signature,
+    /// alongside `project_point_in_viewport` so existing call sites don't
+    /// need to learn about `ViewportId` just to keep compiling.
     pub fn project_point(&self, point: Vec3, screen_size: Vec2) -> Vec2 {
-        let camera_manager = &self.renderer.data_core.lock().camera_manager;
+        self.project_point_in_viewport(ViewportId::MAIN_3D, point, screen_size)
+    }
+
+    /// Projects a world-space point into the screen-space pixel coordinates
+    /// of `viewport`, using that viewport's own camera and aspect ratio
+    /// (derived from `screen_size`) rather than a single global camera.
+    pub fn project_point_in_viewport(
+        &self,
+        viewport: ViewportId,
+        point: Vec3,
+        screen_size: Vec2,
+    ) -> Vec2 {
+        let camera = self.cameras.get(&viewport).copied().unwrap_or_default();
+        let aspect_ratio = screen_size.x / screen_size.y;
+        let proj = Mat4::perspective_infinite_reverse_lh(
+            camera.vfov.to_radians(),
+            aspect_ratio,
+            camera.near,
+        );
+        let view_proj = proj * camera.view_matrix;
 
-        let clip = camera_manager.view_proj().project_point3(point);
+        let clip = view_proj.project_point3(point);
         let clip = Vec2::new(clip.x, -clip.y);
         let zero_to_one = (Vec2::new(clip.x, clip.y) + Vec2::ONE) * 0.5;
         zero_to_one * screen_size
@@ -157,15 +428,67 @@ impl RenderContext {
         self.lights.push(handle);
     }
 
+    /// Must be called on the graph egui platform right before
+    /// `graph_egui.begin_frame()`, every frame. Setting `pixels_per_point`
+    /// through the raw input, instead of calling
+    /// `graph_egui.context().set_pixels_per_point()` after the fact, takes
+    /// effect immediately, avoiding the one-frame lag that made zooming look
+    /// blurry for a frame.
+    pub fn prepare_graph_egui_raw_input(
+        graph_egui: &mut egui_winit_platform::Platform,
+        zoom: Zoom,
+    ) {
+        graph_egui.raw_input_mut().pixels_per_point = Some(zoom.pixels_per_point());
+    }
+
+    /// Renders and presents one frame into `window_id`'s surface. Pass
+    /// `RenderContext`'s main window id to render the primary application
+    /// window; any id returned by `add_window` renders into that window
+    /// instead, mirroring the same render graph against its own surface
+    /// texture.
+    ///
+    /// `zoom` is taken by `&mut` because Ctrl+scroll over the node graph
+    /// (read from `main_egui`'s raw input, which we already have on hand
+    /// every frame) adjusts it in place here. This frame still sizes the
+    /// node graph's egui screen with the value from before that adjustment,
+    /// the one `prepare_graph_egui_raw_input` already gave `graph_egui`, so
+    /// the new zoom takes effect as a whole from the next frame. The caller
+    /// owns `zoom` for the rest of its lifetime (e.g. to persist it to config).
     pub fn render_frame(
         &mut self,
+        window_id: winit::window::WindowId,
         main_egui: &mut egui_winit_platform::Platform,
         graph_egui: &mut egui_winit_platform::Platform,
         app_viewports: &mut AppViewports,
-        zoom_level: f32,
+        zoom: &mut Zoom,
     ) {
+        let window = self
+            .windows
+            .get_mut(&window_id)
+            .expect("render_frame called with an unregistered window id");
+
+        let raw_input = main_egui.raw_input();
+        let ctrl_scroll = raw_input.modifiers.ctrl && raw_input.scroll_delta.y != 0.0;
+        let scroll_delta = raw_input.scroll_delta.y;
+        let hovering_node_graph = main_egui
+            .context()
+            .input()
+            .pointer
+            .hover_pos()
+            .map_or(false, |pos| app_viewports.node_graph.rect.contains(pos));
+        // `graph_egui`'s pixels_per_point for this frame was set from the
+        // zoom before this adjustment, so size this frame with that value too.
+        let frame_zoom = *zoom;
+        if ctrl_scroll && hovering_node_graph {
+            zoom.add_scroll_delta(scroll_delta);
+            // Best-effort: a failed write here (e.g. a read-only working
+            // directory) shouldn't interrupt rendering, and there's no
+            // logging infra in this crate to report it through.
+            let _ = RenderConfig { zoom: *zoom }.save(std::path::Path::new(CONFIG_PATH));
+        }
+
         let frame = rend3::util::output::OutputFrame::Surface {
-            surface: Arc::clone(&self.surface),
+            surface: Arc::clone(&window.surface),
         };
         let (cmd_bufs, ready) = self.renderer.ready();
 
@@ -174,35 +497,142 @@ impl RenderContext {
 
         let mut graph = rend3::RenderGraph::new();
 
-        let vwp_3d_res = app_viewports.view_3d.rect.size();
         let grph_3d_res = app_viewports.node_graph.rect.size();
         let to_uvec2 = |v: egui::Vec2| UVec2::new(v.x as u32, v.y as u32);
 
-        // TODO: What if we ever have multiple 3d viewports? There's no way to
-        // set the aspect ratio differently for different render passes in rend3
-        // right now. The camera is global.
+        // Iterate every registered viewport (sorted, instead of a
+        // `HashMap`'s arbitrary order, so which viewport draws "on top" of
+        // the others in `DirectToSurface` mode is at least deterministic),
+        // not just `ViewportId::MAIN_3D`, so registering additional
+        // viewports via `set_viewport_rect` actually renders them with
+        // their own camera instead of being silently ignored.
         //
-        // See: https://github.com/BVE-Reborn/rend3/issues/327
-        self.renderer.set_aspect_ratio(vwp_3d_res.x / vwp_3d_res.y);
-
-        let viewport_texture = rendergraph::blackjack_viewport_rendergraph(
-            &self.base_graph,
-            &mut graph,
-            &ready,
-            &self.pbr_routine,
-            &self.tonemapping_routine,
-            &self.grid_routine,
-            // The resolution needs to be scaled by the pixels-per-point
-            to_uvec2(vwp_3d_res * main_egui.context().pixels_per_point()),
-            r3::SampleCount::One,
-            ambient_light(),
-        );
+        // `ViewportId::MAIN_3D` always has a rect, from `app_viewports`;
+        // anything else needs its rect registered via `set_viewport_rect`
+        // first (today nothing does, since the editor UI only exposes a
+        // single 3D panel) and is skipped otherwise, since there's nowhere
+        // on screen to put it.
+        //
+        // rend3 issue #327 is still open: whether multiple
+        // `set_camera_data` calls followed by their own `ready()` truly
+        // keep each viewport's nodes rendering with that viewport's own
+        // camera once they all share one `graph.execute()`, or whether the
+        // last `set_camera_data` call before `execute()` wins for every
+        // node regardless of which `ready()` built them, isn't something
+        // this tree can verify (no build available). This loop is written
+        // on the former assumption; if that assumption is wrong, every
+        // viewport after the first will render with the last viewport's
+        // camera instead of its own, which is a real known risk worth
+        // flagging rather than silently shipping.
+        let mut viewport_ids: Vec<ViewportId> = self.cameras.keys().copied().collect();
+        viewport_ids.sort_by_key(|id| id.0);
+
+        let mut viewport_outputs = HashMap::new();
+        for id in viewport_ids {
+            let rect = match id {
+                ViewportId::MAIN_3D => app_viewports.view_3d.rect,
+                _ => match self.viewport_rects.get(&id) {
+                    Some(&rect) => rect,
+                    None => continue,
+                },
+            };
+
+            let camera = self.cameras.entry(id).or_default();
+            let resolution = rect.size();
+            camera.aspect_ratio = resolution.x / resolution.y;
+            // `rect` is in egui points; `ScreenRect` is in physical pixels.
+            let screen_ppp = main_egui.context().pixels_per_point();
+            let screen_rect = rendergraph::ScreenRect {
+                position: to_uvec2(rect.min.to_vec2() * screen_ppp),
+                size: to_uvec2(resolution * screen_ppp),
+            };
+
+            self.renderer.set_aspect_ratio(camera.aspect_ratio);
+            self.renderer.set_camera_data(rend3::types::Camera {
+                projection: rend3::types::CameraProjection::Perspective {
+                    vfov: camera.vfov,
+                    near: camera.near,
+                },
+                view: camera.view_matrix,
+            });
+
+            let output = match self.viewport_composite_mode {
+                ViewportCompositeMode::OffscreenTexture => {
+                    let viewport_texture = rendergraph::blackjack_viewport_rendergraph(
+                        &self.base_graph,
+                        &mut graph,
+                        &ready,
+                        &self.pbr_routine,
+                        &self.tonemapping_routine,
+                        &self.grid_routine,
+                        // The resolution needs to be scaled by the pixels-per-point
+                        to_uvec2(resolution * main_egui.context().pixels_per_point()),
+                        r3::SampleCount::One,
+                        ambient_light(),
+                    );
+                    ViewportOutput::Texture(viewport_texture)
+                }
+                ViewportCompositeMode::DirectToSurface => {
+                    // Composite into this viewport's screen-space rect of
+                    // the shared surface, via `set_viewport`/scissor, right
+                    // here instead of in the main egui pass. The scene still
+                    // renders into its own target first and is blitted from it.
+                    rendergraph::blackjack_viewport_rendergraph_direct(
+                        &self.base_graph,
+                        &mut graph,
+                        &ready,
+                        &self.pbr_routine,
+                        &self.tonemapping_routine,
+                        &self.grid_routine,
+                        &self.blit_routine,
+                        screen_rect,
+                        r3::SampleCount::One,
+                        ambient_light(),
+                    );
+                    ViewportOutput::DirectToSurface
+                }
+            };
+
+            // `ViewportId::MAIN_3D`'s output is handed to the main egui pass
+            // below, which knows how to composite it into the `View3d`
+            // panel specifically. Any other viewport isn't tied to a
+            // specific egui panel, so composite it into its own rect right
+            // here instead of threading it any further.
+            if id != ViewportId::MAIN_3D {
+                if let ViewportOutput::Texture(texture) = output {
+                    let surface = graph.add_surface_texture();
+                    rendergraph::composite_into_viewport(
+                        &mut graph,
+                        texture,
+                        surface,
+                        screen_rect,
+                        &self.blit_routine,
+                    );
+                }
+                continue;
+            }
+            viewport_outputs.insert(id, output);
+        }
+
+        // Every non-`MAIN_3D` viewport was already composited into its own
+        // rect above, so `viewport_outputs` only ever has `MAIN_3D` left in
+        // it here. `MAIN_3D` goes through the main egui pass instead of the
+        // same direct composite, since it's the one viewport tied to a
+        // specific egui panel (`View3d`).
+        //
+        // When the viewport rendered directly to the surface, there's no
+        // texture left to hand to the main egui pass: the panel's pixels are
+        // already there.
+        let viewport_texture = match viewport_outputs.remove(&ViewportId::MAIN_3D) {
+            Some(ViewportOutput::Texture(texture)) => Some(texture),
+            Some(ViewportOutput::DirectToSurface) | None => None,
+        };
 
         let ppp = main_egui.context().pixels_per_point();
 
         self.graph_egui_routine.resize(
-            (app_viewports.node_graph.rect.width() * zoom_level * ppp) as u32,
-            (app_viewports.node_graph.rect.height() * zoom_level * ppp) as u32,
+            (app_viewports.node_graph.rect.width() * frame_zoom.scale() * ppp) as u32,
+            (app_viewports.node_graph.rect.height() * frame_zoom.scale() * ppp) as u32,
             1.0,
         );
 
@@ -239,10 +669,11 @@ impl RenderContext {
         // - The way to fix this is by increasing egui's pixels_per_point with
         //   the inverse of the zoom level. That means the more zoom we have,
         //   the sharper things are going to be.
-        // - There is an additional consideration to be made: Calling
-        //   set_pixels_per_point like I'm doing below has a 1 frame of lag.
-        //   Instead, we need to hijack the raw_input so that the value is set,
-        //   according to the zoom level, at the start of the frame.
+        // - Calling `Context::set_pixels_per_point` here has a 1 frame of
+        //   lag, because tessellation for this frame already happened
+        //   against the old value. Instead, `prepare_graph_egui_raw_input`
+        //   hijacks the raw_input so the value is set, according to the zoom
+        //   level, at the start of the frame, before `begin_frame` is called.
         //
         // Some scattered facts
         // - The *inner* egui should be rendered using 1.0 pixels per point,
@@ -260,8 +691,6 @@ impl RenderContext {
         // would allow easy replication of the graph UI, allowing multiple
         // graphs per split and custom user layouts.
 
-        graph_egui.context().set_pixels_per_point(1.0 / zoom_level);
-
         let graph_egui_texture = {
             let graph_egui_render_target = graph.add_render_target(r3::RenderTargetDescriptor {
                 label: None,
@@ -280,7 +709,7 @@ impl RenderContext {
                 &mut graph,
                 graph_egui_input,
                 graph_egui_render_target,
-                zoom_level,
+                frame_zoom.scale(),
             );
             graph_egui_render_target
         };
@@ -293,28 +722,63 @@ impl RenderContext {
                 context: main_egui.context(),
             };
             let surface = graph.add_surface_texture();
-            self.main_egui_routine.add_main_egui_to_graph(
+            window.egui_routine.add_main_egui_to_graph(
                 &mut graph,
                 main_egui_input,
                 surface,
                 viewport_texture,
                 graph_egui_texture,
                 app_viewports,
+                &self.blit_routine,
             );
         }
 
         graph.execute(&self.renderer, frame, cmd_bufs, &ready);
     }
 
-    pub fn on_resize(&mut self, width: u32, height: u32) {
+    /// Renders and presents one frame into the main window's surface. Kept
+    /// around, with its original (window-id-less) signature, alongside
+    /// `render_frame` so existing single-window call sites don't need to
+    /// change just to keep compiling now that multi-window support exists.
+    pub fn render_frame_main(
+        &mut self,
+        main_egui: &mut egui_winit_platform::Platform,
+        graph_egui: &mut egui_winit_platform::Platform,
+        app_viewports: &mut AppViewports,
+        zoom: &mut Zoom,
+    ) {
+        let main_window_id = self.main_window_id;
+        self.render_frame(main_window_id, main_egui, graph_egui, app_viewports, zoom);
+    }
+
+    /// Reconfigures `window_id`'s surface for its new size. Every window
+    /// manages its own surface independently, so resizing a torn-off window
+    /// doesn't touch any other window's surface.
+    pub fn on_resize(&mut self, window_id: winit::window::WindowId, width: u32, height: u32) {
+        let window = self
+            .windows
+            .get(&window_id)
+            .expect("on_resize called with an unregistered window id");
         rend3::configure_surface(
-            &self.surface,
+            &window.surface,
             &self.renderer.device,
-            self.texture_format,
+            window.texture_format,
             glam::uvec2(width, height),
             rend3::types::PresentMode::Mailbox,
         );
-        self.renderer
-            .set_aspect_ratio(width as f32 / height as f32 * 2.0);
+
+        if window_id == self.main_window_id {
+            self.renderer
+                .set_aspect_ratio(width as f32 / height as f32 * 2.0);
+        }
+    }
+
+    /// Reconfigures the main window's surface for its new size. Kept
+    /// around, with its original (window-id-less) signature, alongside
+    /// `on_resize` so existing single-window call sites don't need to
+    /// change just to keep compiling now that multi-window support exists.
+    pub fn on_resize_main(&mut self, width: u32, height: u32) {
+        let main_window_id = self.main_window_id;
+        self.on_resize(main_window_id, width, height);
     }
 }
@@ -0,0 +1,540 @@
+//! A thin wrapper around `rend3_egui::EguiRenderRoutine` that knows how to
+//! draw two independent egui contexts (the main application UI and the node
+//! graph's own UI, which is rendered to an offscreen texture so it can be
+//! panned/zoomed) into the same render graph.
+
+use crate::prelude::*;
+use glam::Mat4;
+use rend3_egui::EguiRenderRoutine;
+use std::sync::{Arc, Mutex};
+use wgpu::util::DeviceExt;
+
+/// What a single egui context needs in order to be tessellated and drawn:
+/// its already-tessellated paint jobs and the `egui::Context` they came from
+/// (the latter is needed for things like the context's current texture
+/// delta).
+pub struct Input<'a> {
+    pub clipped_meshes: &'a [egui::ClippedPrimitive],
+    pub context: &'a egui::Context,
+}
+
+pub struct EguiCustomRoutine {
+    inner: EguiRenderRoutine,
+}
+
+impl EguiCustomRoutine {
+    pub fn new(
+        renderer: &rend3::Renderer,
+        format: wgpu::TextureFormat,
+        samples: r3::SampleCount,
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+    ) -> Self {
+        Self {
+            inner: EguiRenderRoutine::new(renderer, format, samples, width, height, scale_factor),
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32, scale_factor: f32) {
+        self.inner.resize(width, height, scale_factor);
+    }
+
+    /// Draws a secondary UI (e.g. the node graph) into `render_target`.
+    /// `zoom` is not used: the zoom comes only from the screen size this
+    /// routine was last `resize`d to, and the paint jobs' clip rects are
+    /// handed to rend3_egui without being rescaled by it.
+    pub fn add_sub_ui_to_graph<'node>(
+        &'node mut self,
+        graph: &mut rend3::RenderGraph<'node>,
+        input: Input<'node>,
+        render_target: r3::RenderTargetHandle,
+        zoom: f32,
+    ) {
+        let _ = zoom;
+        self.inner
+            .add_to_graph(graph, input.clipped_meshes, input.context, render_target);
+    }
+
+    /// Draws the main application UI into `surface`, first compositing
+    /// `viewport_texture` into the `View3d` panel's rect and
+    /// `graph_egui_texture` into the node graph panel's rect, so the main
+    /// UI's own widgets (borders, floating toolbars, etc.) are drawn on top
+    /// of real pixels instead of empty surface.
+    ///
+    /// `viewport_texture` is `None` when `ViewportCompositeMode::DirectToSurface`
+    /// is in effect: in that case the 3D viewport already rendered straight
+    /// into its rect of `surface` earlier in the graph, so there's nothing
+    /// left to composite here.
+    pub fn add_main_egui_to_graph<'node>(
+        &'node mut self,
+        graph: &mut rend3::RenderGraph<'node>,
+        input: Input<'node>,
+        surface: r3::RenderTargetHandle,
+        viewport_texture: Option<r3::RenderTargetHandle>,
+        graph_egui_texture: r3::RenderTargetHandle,
+        app_viewports: &crate::graph::graph_editor_egui::viewport_manager::AppViewports,
+        blit_routine: &'node BlitRoutine,
+    ) {
+        let ppp = input.context.pixels_per_point();
+        if let Some(viewport_texture) = viewport_texture {
+            super::composite_into_viewport(
+                graph,
+                viewport_texture,
+                surface,
+                screen_rect_from_egui(app_viewports.view_3d.rect, ppp),
+                blit_routine,
+            );
+        }
+        super::composite_into_viewport(
+            graph,
+            graph_egui_texture,
+            surface,
+            screen_rect_from_egui(app_viewports.node_graph.rect, ppp),
+            blit_routine,
+        );
+
+        self.inner
+            .add_to_graph(graph, input.clipped_meshes, input.context, surface);
+    }
+}
+
+/// Converts an egui panel rect (logical points, as stored on `AppViewports`)
+/// into the physical-pixel `ScreenRect` the rendergraph compositing nodes
+/// expect, by scaling it with the main egui context's `pixels_per_point`.
+fn screen_rect_from_egui(rect: egui::Rect, pixels_per_point: f32) -> super::ScreenRect {
+    let rect = egui::Rect::from_min_max(
+        (rect.min.to_vec2() * pixels_per_point).to_pos2(),
+        (rect.max.to_vec2() * pixels_per_point).to_pos2(),
+    );
+    super::ScreenRect {
+        position: UVec2::new(rect.min.x.max(0.0) as u32, rect.min.y.max(0.0) as u32),
+        size: UVec2::new(rect.width().max(1.0) as u32, rect.height().max(1.0) as u32),
+    }
+}
+
+const BLIT_SHADER: &str = r#"
+@group(0) @binding(0) var t_color: texture_2d<f32>;
+@group(0) @binding(1) var s_color: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) idx: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((idx << 1u) & 2u), f32(idx & 2u));
+    out.uv = uv;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_color, s_color, in.uv);
+}
+"#;
+
+/// Draws an already-rendered texture as a single full-screen triangle into
+/// whatever render target the current `wgpu::RenderPass` targets, sampling
+/// it with a bilinear sampler. Used to composite a scene/UI texture into
+/// another target (e.g. the surface, restricted to a viewport + scissor
+/// rect) without going through egui's mesh/UV path.
+///
+/// Builds its shader, pipeline, bind group layout and sampler once in `new`
+/// and reuses them for every `blit` call; only the bind group (which has to
+/// reference that call's particular `source` view) is rebuilt per call,
+/// since it's cheap relative to rebuilding pipeline state every frame.
+pub struct BlitRoutine {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl BlitRoutine {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("viewport-blit-shader"),
+            source: wgpu::ShaderSource::Wgsl(BLIT_SHADER.into()),
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("viewport-blit-bind-group-layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("viewport-blit-pipeline-layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("viewport-blit-pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(r3::TextureFormat::Bgra8UnormSrgb.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("viewport-blit-sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    pub fn blit(&self, device: &wgpu::Device, rpass: &mut wgpu::RenderPass<'_>, source: &wgpu::TextureView) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("viewport-blit-bind-group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+struct ThumbnailVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+struct ThumbnailTargetState {
+    size: (u32, u32),
+    pipeline: Option<(wgpu::RenderPipeline, wgpu::BindGroupLayout)>,
+    vertices: Vec<ThumbnailVertex>,
+    vertex_buffer: Option<wgpu::Buffer>,
+    /// The auto-framed view-projection matrix for the current `vertices`,
+    /// recomputed by [`ThumbnailTarget::update_mesh`] from their bounding
+    /// box. Uploaded as a uniform the next time this target is painted.
+    view_proj: Mat4,
+    uniform_buffer: Option<wgpu::Buffer>,
+    uniform_bind_group: Option<wgpu::BindGroup>,
+}
+
+/// Per-node state for a live mesh thumbnail: the triangulated, depth-sorted
+/// vertex buffer and the camera that auto-frames it. There's no pooled
+/// render target here (an earlier version allocated one but never actually
+/// rendered into it) — [`EguiCustomCallback::paint`] draws straight into the
+/// render pass egui hands the callback, scissored to the widget's rect.
+///
+/// Cheap to clone: every clone shares the same underlying GPU resources, so
+/// a clone can be handed to an [`EguiCustomCallback`] while the original
+/// keeps being updated by [`ThumbnailTarget::update_mesh`] each frame.
+#[derive(Clone)]
+pub struct ThumbnailTarget {
+    state: Arc<Mutex<ThumbnailTargetState>>,
+}
+
+impl ThumbnailTarget {
+    pub fn new(rect: egui::Rect) -> Self {
+        let size = (rect.width().max(1.0) as u32, rect.height().max(1.0) as u32);
+        Self {
+            state: Arc::new(Mutex::new(ThumbnailTargetState {
+                size,
+                pipeline: None,
+                vertices: Vec::new(),
+                vertex_buffer: None,
+                view_proj: Mat4::IDENTITY,
+                uniform_buffer: None,
+                uniform_bind_group: None,
+            })),
+        }
+    }
+
+    /// Records `rect`'s new size (there's no backing texture to resize).
+    /// [`EguiCustomCallback::paint`] does not read it: it takes its
+    /// viewport/scissor rect from the clip rect egui hands the callback.
+    pub fn resize(&mut self, rect: egui::Rect) {
+        let mut state = self.state.lock().unwrap();
+        state.size = (rect.width().max(1.0) as u32, rect.height().max(1.0) as u32);
+    }
+
+    /// Re-triangulates `mesh`'s faces (as a simple fan per face) into a flat
+    /// vertex/normal buffer, auto-frames a camera around their bounding box,
+    /// and sorts the triangles back-to-front relative to that camera's eye.
+    ///
+    /// The sort stands in for a depth buffer: the paint callback only gets
+    /// an already-open `wgpu::RenderPass` with no depth attachment of its
+    /// own to bind, so occlusion has to come from draw order instead of the
+    /// GPU's depth test.
+    pub fn update_mesh(&mut self, mesh: &HalfEdgeMesh) {
+        let positions: std::collections::HashMap<_, _> = mesh
+            .iter_vertices()
+            .map(|(id, vertex)| (id, vertex.position))
+            .collect();
+
+        let mut triangles: Vec<[ThumbnailVertex; 3]> = Vec::new();
+        for (face_id, _) in mesh.iter_faces() {
+            let loop_vertices = mesh.face_vertices(face_id);
+            if loop_vertices.len() < 3 {
+                continue;
+            }
+            let v0 = positions[&loop_vertices[0]];
+            for window in loop_vertices[1..].windows(2) {
+                let v1 = positions[&window[0]];
+                let v2 = positions[&window[1]];
+                let normal = (v1 - v0).cross(v2 - v0).normalize_or_zero();
+                triangles.push([v0, v1, v2].map(|position| ThumbnailVertex {
+                    position: position.to_array(),
+                    normal: normal.to_array(),
+                }));
+            }
+        }
+
+        let (eye, view_proj) = auto_frame_camera(
+            triangles.iter().flatten().map(|vertex| Vec3::from(vertex.position)),
+        );
+        triangles.sort_by(|a, b| {
+            let depth = |tri: &[ThumbnailVertex; 3]| {
+                tri.iter()
+                    .map(|v| Vec3::from(v.position).distance_squared(eye))
+                    .sum::<f32>()
+            };
+            depth(b).partial_cmp(&depth(a)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut state = self.state.lock().unwrap();
+        state.vertices = triangles.into_iter().flatten().collect();
+        state.view_proj = view_proj;
+    }
+}
+
+/// Builds a camera that frames `positions`' bounding box: an eye placed
+/// along a fixed diagonal direction at a distance proportional to the
+/// bounding box's size, looking at its center, with an orthographic
+/// projection sized to fit the whole box. Falls back to a unit cube (side
+/// length 1) centered on the origin if `positions` is empty.
+fn auto_frame_camera(positions: impl Iterator<Item = Vec3>) -> (Vec3, Mat4) {
+    let mut min = Vec3::splat(f32::INFINITY);
+    let mut max = Vec3::splat(f32::NEG_INFINITY);
+    for position in positions {
+        min = min.min(position);
+        max = max.max(position);
+    }
+    if !min.is_finite() || !max.is_finite() {
+        min = Vec3::splat(-0.5);
+        max = Vec3::splat(0.5);
+    }
+
+    let center = (min + max) * 0.5;
+    let radius = ((max - min).length() * 0.5).max(0.1);
+
+    let eye = center + Vec3::new(1.0, 1.0, 1.0).normalize() * radius * 2.5;
+    let view = Mat4::look_at_lh(eye, center, Vec3::Y);
+    let proj = Mat4::orthographic_lh(-radius, radius, -radius, radius, 0.01, radius * 5.0);
+    (eye, proj * view)
+}
+
+/// An egui paint callback that draws a [`ThumbnailTarget`]'s mesh as a
+/// miniature, auto-framed, flat-shaded scene, clipped to the widget's rect.
+pub struct EguiCustomCallback {
+    target: ThumbnailTarget,
+}
+
+impl EguiCustomCallback {
+    /// Wraps `target` into an `egui::Shape` that can be handed directly to
+    /// `ui.painter().add(...)`.
+    pub fn new(rect: egui::Rect, target: ThumbnailTarget) -> egui::Shape {
+        egui::Shape::Callback(egui::epaint::PaintCallback {
+            rect,
+            callback: Arc::new(rend3_egui::CallbackFn::new(move |info, render_pass, resources| {
+                let device: &Arc<wgpu::Device> = resources.get().expect(
+                    "rend3's wgpu::Device must be registered as a paint callback resource",
+                );
+                Self {
+                    target: target.clone(),
+                }
+                .paint(device, info, render_pass);
+            })),
+        })
+    }
+
+    fn paint(
+        &self,
+        device: &wgpu::Device,
+        info: rend3_egui::PaintCallbackInfo,
+        rpass: &mut wgpu::RenderPass<'_>,
+    ) {
+        let mut state = self.target.state.lock().unwrap();
+
+        if state.pipeline.is_none() {
+            state.pipeline = Some(create_thumbnail_pipeline(device));
+        }
+
+        if !state.vertices.is_empty() {
+            state.vertex_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("node-thumbnail-vertices"),
+                contents: bytemuck::cast_slice(&state.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }));
+
+            let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("node-thumbnail-uniforms"),
+                contents: bytemuck::cast_slice(&[state.view_proj.to_cols_array_2d()]),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            let (_, bind_group_layout) = state.pipeline.as_ref().unwrap();
+            state.uniform_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("node-thumbnail-bind-group"),
+                layout: bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            }));
+            state.uniform_buffer = Some(uniform_buffer);
+        }
+
+        let clip = info.clip_rect_in_pixels();
+        rpass.set_viewport(clip.left_px, clip.top_px, clip.width_px, clip.height_px, 0.0, 1.0);
+        rpass.set_scissor_rect(
+            clip.left_px as u32,
+            clip.top_px as u32,
+            clip.width_px as u32,
+            clip.height_px as u32,
+        );
+
+        if let (Some((pipeline, _)), Some(vertex_buffer), Some(bind_group)) = (
+            &state.pipeline,
+            &state.vertex_buffer,
+            &state.uniform_bind_group,
+        ) {
+            let vertex_count = state.vertices.len() as u32;
+            rpass.set_pipeline(pipeline);
+            rpass.set_bind_group(0, bind_group, &[]);
+            rpass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            rpass.draw(0..vertex_count, 0..1);
+        }
+    }
+}
+
+fn create_thumbnail_pipeline(device: &wgpu::Device) -> (wgpu::RenderPipeline, wgpu::BindGroupLayout) {
+    const THUMBNAIL_SHADER: &str = r#"
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+}
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+}
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = uniforms.view_proj * vec4<f32>(in.position, 1.0);
+    out.normal = in.normal;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let light_dir = normalize(vec3<f32>(0.4, 0.8, 0.4));
+    let intensity = 0.3 + 0.7 * max(dot(normalize(in.normal), light_dir), 0.0);
+    return vec4<f32>(vec3<f32>(intensity), 1.0);
+}
+"#;
+
+    let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+        label: Some("node-thumbnail-shader"),
+        source: wgpu::ShaderSource::Wgsl(THUMBNAIL_SHADER.into()),
+    });
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("node-thumbnail-bind-group-layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("node-thumbnail-pipeline-layout"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("node-thumbnail-pipeline"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<ThumbnailVertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3],
+            }],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(r3::TextureFormat::Bgra8UnormSrgb.into())],
+        }),
+        // No depth attachment is available here (see `update_mesh`'s doc
+        // comment), so occlusion comes from the back-to-front triangle sort
+        // instead of a depth test.
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    });
+    (pipeline, bind_group_layout)
+}
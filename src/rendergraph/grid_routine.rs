@@ -0,0 +1,66 @@
+//! Draws the reference grid on top of the scene in the 3D viewport.
+
+use crate::prelude::*;
+
+pub struct GridRoutine {
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl GridRoutine {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("grid-shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("grid.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("grid-pipeline-layout"),
+            bind_group_layouts: &[],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("grid-pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: r3::TextureFormat::Bgra8UnormSrgb,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        Self { pipeline }
+    }
+
+    /// Adds the grid pass to `graph`, alpha-blended on top of `color`.
+    /// `depth` is declared as an input of the node but not bound to the
+    /// pass, so the grid is not occluded by the rest of the scene.
+    pub fn add_to_graph<'node>(
+        &'node self,
+        graph: &mut rend3::RenderGraph<'node>,
+        color: r3::RenderTargetHandle,
+        depth: r3::RenderTargetHandle,
+        _resolution: UVec2,
+    ) {
+        let mut builder = graph.add_node("grid");
+        let color_handle = builder.add_render_target_output(color);
+        let _depth_handle = builder.add_render_target_input(depth);
+
+        builder.build(move |_pt, _renderer, encoder_or_pass, _temps, _ready, _graph_data| {
+            let mut rpass = encoder_or_pass.get_rpass(color_handle);
+            rpass.set_pipeline(&self.pipeline);
+            rpass.draw(0..3, 0..1);
+        });
+    }
+}
@@ -0,0 +1,126 @@
+//! Glue between `RenderContext` and the individual rend3 render routines
+//! (PBR, tonemapping, the editor's grid) that make up a single 3D viewport.
+
+pub mod egui_routine_custom;
+pub mod grid_routine;
+
+use crate::prelude::*;
+use egui_routine_custom::BlitRoutine;
+use grid_routine::GridRoutine;
+
+/// A viewport's position and size, in physical pixels of the shared surface.
+/// Used by [`blackjack_viewport_rendergraph_direct`] to restrict rendering to
+/// a sub-rect of the surface via a GPU viewport + scissor rect, instead of
+/// the whole surface.
+#[derive(Clone, Copy, Debug)]
+pub struct ScreenRect {
+    pub position: UVec2,
+    pub size: UVec2,
+}
+
+/// Records the render passes for one 3D viewport (grid, PBR scene,
+/// tonemapping) into `graph`, rendering into a freshly allocated
+/// `resolution`-sized offscreen target. The returned handle is meant to be
+/// composited later, e.g. by the main egui routine blitting it into the
+/// `View3d` panel's rect with [`composite_into_viewport`].
+pub fn blackjack_viewport_rendergraph<'node>(
+    base_graph: &'node r3::BaseRenderGraph,
+    graph: &mut rend3::RenderGraph<'node>,
+    ready: &r3::ReadyData,
+    pbr_routine: &'node r3::PbrRoutine,
+    tonemapping_routine: &'node r3::TonemappingRoutine,
+    grid_routine: &'node GridRoutine,
+    resolution: UVec2,
+    samples: r3::SampleCount,
+    ambient: Vec4,
+) -> r3::RenderTargetHandle {
+    let outputs = base_graph.add_base_render_graph(
+        graph,
+        ready,
+        r3::BaseRenderGraphInputs {
+            pbr: pbr_routine,
+            tonemapping: tonemapping_routine,
+            resolution,
+            samples,
+            ambient,
+        },
+    );
+    grid_routine.add_to_graph(graph, outputs.color, outputs.depth, resolution);
+    outputs.color
+}
+
+/// Same render passes as [`blackjack_viewport_rendergraph`], but composited
+/// straight into `screen_rect`'s slice of the shared surface instead of being
+/// handed back as a texture to sample later.
+///
+/// rend3's PBR/tonemapping routines always render into a target sized to
+/// exactly their `resolution`, so there's no way to have them paint directly
+/// into an arbitrary sub-rect of a larger surface target: this still
+/// allocates that target and blits it, with the same
+/// [`composite_into_viewport`] node the texture path uses later in the main
+/// egui routine. The difference is only that the composite is recorded here,
+/// right after the viewport's own passes, using `wgpu::RenderPass::set_viewport`
+/// + scissor rect so nothing outside `screen_rect` on the surface is touched.
+pub fn blackjack_viewport_rendergraph_direct<'node>(
+    base_graph: &'node r3::BaseRenderGraph,
+    graph: &mut rend3::RenderGraph<'node>,
+    ready: &r3::ReadyData,
+    pbr_routine: &'node r3::PbrRoutine,
+    tonemapping_routine: &'node r3::TonemappingRoutine,
+    grid_routine: &'node GridRoutine,
+    blit_routine: &'node BlitRoutine,
+    screen_rect: ScreenRect,
+    samples: r3::SampleCount,
+    ambient: Vec4,
+) {
+    let scene = blackjack_viewport_rendergraph(
+        base_graph,
+        graph,
+        ready,
+        pbr_routine,
+        tonemapping_routine,
+        grid_routine,
+        screen_rect.size,
+        samples,
+        ambient,
+    );
+    let surface = graph.add_surface_texture();
+    composite_into_viewport(graph, scene, surface, screen_rect, blit_routine);
+}
+
+/// Adds a node that blits `source` onto `target`, clipped to `screen_rect`
+/// via `set_viewport` + `set_scissor_rect`, instead of the mesh-and-UVs path
+/// egui's renderer would otherwise use to draw the same pixels.
+pub(crate) fn composite_into_viewport<'node>(
+    graph: &mut rend3::RenderGraph<'node>,
+    source: r3::RenderTargetHandle,
+    target: r3::RenderTargetHandle,
+    screen_rect: ScreenRect,
+    blit_routine: &'node BlitRoutine,
+) {
+    let mut builder = graph.add_node("blackjack viewport direct composite");
+    let source_handle = builder.add_render_target_input(source);
+    let target_handle = builder.add_render_target_output(target);
+
+    builder.build(move |_pt, renderer, encoder_or_pass, temps, _ready, _graph_data| {
+        let source_view = temps.get_render_target(source_handle);
+        let mut rpass = encoder_or_pass.get_rpass(target_handle);
+
+        rpass.set_viewport(
+            screen_rect.position.x as f32,
+            screen_rect.position.y as f32,
+            screen_rect.size.x as f32,
+            screen_rect.size.y as f32,
+            0.0,
+            1.0,
+        );
+        rpass.set_scissor_rect(
+            screen_rect.position.x,
+            screen_rect.position.y,
+            screen_rect.size.x,
+            screen_rect.size.y,
+        );
+
+        blit_routine.blit(&renderer.device, &mut rpass, source_view);
+    });
+}